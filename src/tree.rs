@@ -1,37 +1,197 @@
+use std::collections::{HashSet, VecDeque};
+
 #[derive(Debug, Clone)]
 pub struct Node<T>{
     depth_level: usize,
     parent_id: Option<usize>,
     children_id: Vec<usize>,
-    value: T
+    value: T,
+    /// Cached count of this node plus all of its descendants, kept up to date
+    /// incrementally by `add`/`remove` so `subtree_size` is O(1).
+    subtree_size: usize,
+}
+
+/// Controls what happens to a removed node's children when `remove` is called.
+pub enum OrphanPolicy{
+    /// Drop the whole subtree rooted at the removed node.
+    DropSubtree,
+    /// Reattach the removed node's children to its former parent, shifting
+    /// their `depth_level` up by one to account for the skipped generation.
+    ReattachChildren,
+}
+
+/// Identifies a checkpoint created by [`Tree::checkpoint`].
+pub type CheckpointId = usize;
+
+/// An inverse of a single `add`/`remove` mutation, journaled so [`Tree::rewind`] can
+/// replay it to undo that mutation.
+enum UndoOp<T>{
+    /// Undoes an `add`: removes the node at `id` (and reclaims its slot).
+    UndoAdd{ id: usize },
+    /// Undoes one node freed by a `remove_subtree` call. `relink` is only set for the
+    /// node that was directly detached from its (still-alive) parent; descendants don't
+    /// need relinking because their local parent's `children_id` was never touched.
+    UndoRemove{
+        id: usize,
+        node: Node<T>,
+        size_anchor: Option<usize>,
+        relink: Option<(usize, usize)>,
+    },
+    /// Undoes a `remove` performed with `OrphanPolicy::ReattachChildren`.
+    UndoReattach{
+        id: usize,
+        node: Node<T>,
+        parent_id: Option<usize>,
+        child_index: usize,
+        reattached_children: Vec<usize>,
+    },
 }
 
 pub struct Tree<T>{
-    nodes: Vec<Node<T>>,
+    nodes: Vec<Option<Node<T>>>,
+    free: Vec<usize>,
+    /// The id of the current canonical root. Normally `0`, but `finalize` can re-root
+    /// the tree at an arbitrary id, so methods that implicitly start "from the root"
+    /// (`matches_branch`, `find_prefixes`, `prune_where`, ...) must go through this
+    /// instead of hardcoding `0`.
+    root_id: usize,
+    /// Stacked per-checkpoint undo journals; the last segment is the most recent checkpoint.
+    journal: Vec<Vec<UndoOp<T>>>,
+    next_checkpoint_id: CheckpointId,
+    max_checkpoints: Option<usize>,
+}
+
+/// Breadth-first iterator over a subtree, yielding `(id, node)` pairs level by level.
+///
+/// Built by [`Tree::iter_bfs`].
+pub struct BfsIter<'a, T>{
+    tree: &'a Tree<T>,
+    frontier: VecDeque<usize>,
+}
+
+impl<'a, T> Iterator for BfsIter<'a, T>{
+    type Item = (usize, &'a Node<T>);
+
+    fn next(&mut self)->Option<Self::Item>{
+        while let Some(id) = self.frontier.pop_front(){
+            if let Some(Some(node)) = self.tree.nodes.get(id){
+                self.frontier.extend(node.children_id.iter().copied());
+                return Some((id, node));
+            }
+        }
+        None
+    }
+}
+
+/// Pre-order depth-first iterator over a subtree, yielding `(id, node)` pairs.
+///
+/// Uses an explicit stack instead of recursion, so deep trees don't blow the call stack.
+/// Built by [`Tree::iter_dfs_preorder`].
+pub struct DfsPreorderIter<'a, T>{
+    tree: &'a Tree<T>,
+    stack: Vec<usize>,
+}
+
+impl<'a, T> Iterator for DfsPreorderIter<'a, T>{
+    type Item = (usize, &'a Node<T>);
+
+    fn next(&mut self)->Option<Self::Item>{
+        while let Some(id) = self.stack.pop(){
+            if let Some(Some(node)) = self.tree.nodes.get(id){
+                self.stack.extend(node.children_id.iter().rev().copied());
+                return Some((id, node));
+            }
+        }
+        None
+    }
+}
+
+/// Post-order depth-first iterator over a subtree, yielding `(id, node)` pairs.
+///
+/// The visiting order is computed upfront with an explicit stack (not recursion), then
+/// replayed lazily. Built by [`Tree::iter_dfs_postorder`].
+pub struct DfsPostorderIter<'a, T>{
+    tree: &'a Tree<T>,
+    order: std::vec::IntoIter<usize>,
+}
+
+impl<'a, T> Iterator for DfsPostorderIter<'a, T>{
+    type Item = (usize, &'a Node<T>);
+
+    fn next(&mut self)->Option<Self::Item>{
+        self.order.next().map(|id| (id, self.tree.node(id)))
+    }
 }
 
 impl<T> Tree<T>{
     pub fn new_empty()->Tree<T>{
         Tree{
             nodes: Vec::new(),
+            free: Vec::new(),
+            root_id: 0,
+            journal: Vec::new(),
+            next_checkpoint_id: 0,
+            max_checkpoints: None,
         }
     }
     pub fn new(value: T)->Tree<T>{
-        let node = Node { depth_level: 0, parent_id: None, value: value, children_id: Vec::new() };
+        let node = Node { depth_level: 0, parent_id: None, value: value, children_id: Vec::new(), subtree_size: 1 };
         Tree{
-            nodes: vec![node],
+            nodes: vec![Some(node)],
+            free: Vec::new(),
+            root_id: 0,
+            journal: Vec::new(),
+            next_checkpoint_id: 0,
+            max_checkpoints: None,
         }
     }
 
+    /// Returns the tree itself, bounding the number of stacked checkpoints to `max`
+    /// (chainable, like `add`). Once exceeded, the oldest journal segment is dropped,
+    /// so `rewind` can no longer undo past that point.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of stacked checkpoints to retain
+    pub fn with_max_checkpoints(mut self, max: usize)->Tree<T>{
+        self.max_checkpoints = Some(max);
+        while self.journal.len() > max{
+            self.journal.remove(0);
+        }
+        self
+    }
+
+    /// Returns a reference to the node at `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is out of bounds or refers to a removed slot.
+    fn node(&self, id: usize)->&Node<T>{
+        self.nodes[id].as_ref().expect("node id refers to a removed or invalid slot")
+    }
+
+    /// Returns a mutable reference to the node at `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is out of bounds or refers to a removed slot.
+    fn node_mut(&mut self, id: usize)->&mut Node<T>{
+        self.nodes[id].as_mut().expect("node id refers to a removed or invalid slot")
+    }
+
+    /// Returns whether `id` currently points at a live node
+    fn is_live(&self, id: usize)->bool{
+        matches!(self.nodes.get(id), Some(Some(_)))
+    }
 
     /// Returns the max depth of the tree
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `usize` - The max depth of the tree
     pub fn get_current_max_depth(&self)->usize{
         let mut max_depth = 0;
-        for node in &self.nodes{
+        for node in self.nodes.iter().flatten(){
             if node.depth_level > max_depth{
                 max_depth = node.depth_level;
             }
@@ -42,25 +202,201 @@ impl<T> Tree<T>{
 
     pub fn get_children(&self, parent_id: usize)->Vec<&Node<T>>{
         let mut children = Vec::new();
-        for child_id in &self.nodes[parent_id].children_id{
-            children.push(&self.nodes[*child_id]);
+        for child_id in &self.node(parent_id).children_id{
+            children.push(self.node(*child_id));
         }
         children
     }
 
+    /// Returns a breadth-first iterator over `root_id` and its descendants.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the node to start the walk from
+    pub fn iter_bfs(&self, root_id: usize)->BfsIter<'_, T>{
+        let mut frontier = VecDeque::new();
+        frontier.push_back(root_id);
+        BfsIter{ tree: self, frontier }
+    }
+
+    /// Returns a pre-order depth-first iterator over `root_id` and its descendants.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the node to start the walk from
+    pub fn iter_dfs_preorder(&self, root_id: usize)->DfsPreorderIter<'_, T>{
+        DfsPreorderIter{ tree: self, stack: vec![root_id] }
+    }
+
+    /// Returns a post-order depth-first iterator over `root_id` and its descendants.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the node to start the walk from
+    pub fn iter_dfs_postorder(&self, root_id: usize)->DfsPostorderIter<'_, T>{
+        let mut stack = vec![root_id];
+        let mut order = Vec::new();
+        while let Some(id) = stack.pop(){
+            if let Some(Some(node)) = self.nodes.get(id){
+                order.push(id);
+                stack.extend(node.children_id.iter().copied());
+            }
+        }
+        order.reverse();
+        DfsPostorderIter{ tree: self, order: order.into_iter() }
+    }
+
+    /// Returns the id of the first node within `root_id`'s subtree (breadth-first order)
+    /// matching `predicate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the subtree root to search within
+    /// * `predicate` - The predicate each node's value is tested against
+    pub fn find_bfs(&self, root_id: usize, predicate: &dyn Fn(&T)->bool)->Option<usize>{
+        self.iter_bfs(root_id).find(|(_, node)| predicate(&node.value)).map(|(id, _)| id)
+    }
+
+    /// Returns the id of the first node within `root_id`'s subtree (pre-order depth-first
+    /// order) matching `predicate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the subtree root to search within
+    /// * `predicate` - The predicate each node's value is tested against
+    pub fn find_dfs_preorder(&self, root_id: usize, predicate: &dyn Fn(&T)->bool)->Option<usize>{
+        self.iter_dfs_preorder(root_id).find(|(_, node)| predicate(&node.value)).map(|(id, _)| id)
+    }
+
+    /// Post-order folds `f` over the values of `root_id` and all of its descendants.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the subtree root to fold over
+    /// * `init` - The initial accumulator value
+    /// * `f` - Combines the running accumulator with each visited value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // borrowed from the classic directory-sizing pattern: a directory's size is
+    /// // the sum of its descendants' file sizes
+    /// let total_size = tree.fold_subtree(root_id, 0, |acc, file_size| acc + file_size);
+    /// ```
+    pub fn fold_subtree<A>(&self, root_id: usize, init: A, f: impl Fn(A, &T)->A)->A{
+        let mut acc = init;
+        for (_, node) in self.iter_dfs_postorder(root_id){
+            acc = f(acc, &node.value);
+        }
+        acc
+    }
+
+    /// Returns the cached node count of `root_id`'s subtree, including `root_id` itself
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the subtree root
+    pub fn subtree_size(&self, root_id: usize)->usize{
+        self.node(root_id).subtree_size
+    }
+
+    /// Returns the ids of every descendant of `root_id`, in breadth-first order
+    ///
+    /// # Arguments
+    ///
+    /// * `root_id` - The id of the node whose descendants are collected
+    pub fn descendants(&self, root_id: usize)->Vec<usize>{
+        self.iter_bfs(root_id)
+            .map(|(id, _)| id)
+            .filter(|&id| id != root_id)
+            .collect()
+    }
+
+    /// Returns the lowest common ancestor of `a` and `b`, walking both up via `parent_id`
+    /// until they meet. If one node is an ancestor of the other, that node is the LCA.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The id of the first node
+    /// * `b` - The id of the second node
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The id of the lowest common ancestor, or `None` if either id
+    ///     refers to a removed or invalid slot
+    pub fn lowest_common_ancestor(&self, a: usize, b: usize)->Option<usize>{
+        if !self.is_live(a) || !self.is_live(b){
+            return None;
+        }
+
+        let mut a = a;
+        let mut b = b;
+        let mut depth_a = self.node(a).depth_level;
+        let mut depth_b = self.node(b).depth_level;
+
+        while depth_a > depth_b{
+            a = self.node(a).parent_id?;
+            depth_a -= 1;
+        }
+        while depth_b > depth_a{
+            b = self.node(b).parent_id?;
+            depth_b -= 1;
+        }
+        while a != b{
+            a = self.node(a).parent_id?;
+            b = self.node(b).parent_id?;
+        }
+        Some(a)
+    }
+
+    /// Returns the path from `a` to `b`, going up to their lowest common ancestor and
+    /// back down.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The id of the starting node
+    /// * `b` - The id of the destination node
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Vec<usize>>` - The ids forming the path from `a` to `b` inclusive, or
+    ///     `None` if either id refers to a removed or invalid slot
+    pub fn path_between(&self, a: usize, b: usize)->Option<Vec<usize>>{
+        let lca = self.lowest_common_ancestor(a, b)?;
+
+        let mut up_from_a = Vec::new();
+        let mut current = a;
+        while current != lca{
+            up_from_a.push(current);
+            current = self.node(current).parent_id?;
+        }
+        up_from_a.push(lca);
+
+        let mut down_to_b = Vec::new();
+        let mut current = b;
+        while current != lca{
+            down_to_b.push(current);
+            current = self.node(current).parent_id?;
+        }
+        down_to_b.reverse();
+
+        up_from_a.extend(down_to_b);
+        Some(up_from_a)
+    }
+
     /// Returns the node id of the children that matches the value of the parent node
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `parent_id` - The id of the parent node
     /// * `value` - The value of the child node
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Option<usize>` - The id of the child node, Some value or None, whether it matched or not
     pub fn matches_children(&self, parent_id: usize, value:T)->Option<usize> where T: PartialEq{
-        for child_id in &self.nodes[parent_id].children_id{
-            if self.nodes[*child_id].value == value{
+        for child_id in &self.node(parent_id).children_id{
+            if self.node(*child_id).value == value{
                 return Some(*child_id);
             }
         }
@@ -69,22 +405,22 @@ impl<T> Tree<T>{
 
     /// Returns the tree itself, with the new node added (this method is chainable and
     /// takes ownership of the tree)
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `value` - The value of the new node
     /// * `parent_id` - The id of the parent node of the new node
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Tree<T>` - The tree itself, with the new node added
-    /// 
+    ///
     /// # Example
-    /// 
+    ///
     /// ```
     /// let tree = Tree::<u32>::new(0);
     /// let tree = tree.add(1, 0).add(2, 1).add(3, 2); // chainable
-    /// 
+    ///
     /// assert_eq!(tree.nodes.len(), 4);
     /// assert_eq!(tree.nodes[0].value, 0);
     /// assert_eq!(tree.nodes[1].value, 1);
@@ -93,23 +429,245 @@ impl<T> Tree<T>{
     /// ```
     pub fn add(mut self, value: T, parent_id: usize)->Tree<T>{
         let node = Node {
-            depth_level: self.nodes[parent_id].depth_level + 1,
+            depth_level: self.node(parent_id).depth_level + 1,
             parent_id: Some(parent_id),
             value: value,
-            children_id: Vec::new()
+            children_id: Vec::new(),
+            subtree_size: 1,
         };
-        self.nodes.push(node);
 
-        let length_of_nodes = self.nodes.len();
-        let mut parent = &mut self.nodes[parent_id];
-        parent.children_id.push(length_of_nodes - 1);
+        // reuse a slot freed by a previous removal before growing the vec,
+        // so ids stay stable and the tree doesn't grow unbounded under churn
+        let new_id = if let Some(free_id) = self.free.pop(){
+            self.nodes[free_id] = Some(node);
+            free_id
+        }else{
+            self.nodes.push(Some(node));
+            self.nodes.len() - 1
+        };
+
+        self.node_mut(parent_id).children_id.push(new_id);
+        self.adjust_subtree_size(Some(parent_id), 1);
+        self.record(UndoOp::UndoAdd{ id: new_id });
         self
     }
 
+    /// Applies `delta` to the cached `subtree_size` of `id` and every one of its ancestors
+    fn adjust_subtree_size(&mut self, mut id: Option<usize>, delta: isize){
+        while let Some(current_id) = id{
+            let node = self.node_mut(current_id);
+            node.subtree_size = (node.subtree_size as isize + delta) as usize;
+            id = node.parent_id;
+        }
+    }
+
+    /// Detaches `id` from its parent's `children_id` and removes it according to `policy`,
+    /// reclaiming the vacated slots so future `add` calls can reuse them.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the node to remove
+    /// * `policy` - What to do with the removed node's children
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether a live node was found and removed. Removing the canonical
+    ///   root (`self.root_id`) is unsupported and always returns `false`, since every
+    ///   root-anchored method (`matches_branch`, `find_prefixes`, `prune_where`, ...)
+    ///   assumes `self.root_id` always refers to a live node.
+    pub fn remove(&mut self, id: usize, policy: OrphanPolicy)->bool{
+        if !self.is_live(id) || id == self.root_id{
+            return false;
+        }
+
+        match policy{
+            OrphanPolicy::DropSubtree => self.remove_subtree(id),
+            OrphanPolicy::ReattachChildren => {
+                let parent_id = self.node(id).parent_id;
+                let child_index = parent_id.and_then(|pid| self.node(pid).children_id.iter().position(|&c| c == id));
+                let children_id = self.node(id).children_id.clone();
+
+                for &child_id in &children_id{
+                    let child = self.node_mut(child_id);
+                    child.parent_id = parent_id;
+                    child.depth_level = child.depth_level.saturating_sub(1);
+                }
+
+                self.detach_from_parent(id);
+                if let Some(parent_id) = parent_id{
+                    self.node_mut(parent_id).children_id.extend(children_id.clone());
+                }
+                let node = self.nodes[id].take().unwrap();
+                self.free.push(id);
+                self.adjust_subtree_size(parent_id, -1);
+
+                self.record(UndoOp::UndoReattach{
+                    id,
+                    node,
+                    parent_id,
+                    child_index: child_index.unwrap_or(0),
+                    reattached_children: children_id,
+                });
+                true
+            }
+        }
+    }
+
+    /// Removes `id` and every one of its descendants, reclaiming their slots.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the subtree root to remove
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether a live node was found and removed. Removing the canonical
+    ///   root (`self.root_id`) is unsupported and always returns `false`, for the same
+    ///   reason as [`Tree::remove`].
+    pub fn remove_subtree(&mut self, id: usize)->bool{
+        if id == self.root_id{
+            return false;
+        }
+        match self.detach_and_free_subtree(id){
+            None => false,
+            Some((parent_id, child_index, removed)) => {
+                for (removed_id, node) in removed{
+                    let relink = if removed_id == id{
+                        child_index.map(|idx| (parent_id.unwrap(), idx))
+                    }else{
+                        None
+                    };
+                    self.record(UndoOp::UndoRemove{ id: removed_id, node, size_anchor: parent_id, relink });
+                }
+                true
+            }
+        }
+    }
+
+    /// Detaches `id` from its (still-alive) parent and frees every node in its subtree,
+    /// adjusting cached subtree sizes along the way. Returns the freed `(id, node)` pairs
+    /// in the order they were taken (`id` itself always comes first), along with the
+    /// parent id and child-list index `id` used to occupy, so callers can journal or
+    /// discard them as needed.
+    fn detach_and_free_subtree(&mut self, id: usize)->Option<(Option<usize>, Option<usize>, Vec<(usize, Node<T>)>)>{
+        if !self.is_live(id){
+            return None;
+        }
+
+        let parent_id = self.node(id).parent_id;
+        let child_index = parent_id.and_then(|pid| self.node(pid).children_id.iter().position(|&c| c == id));
+        self.detach_from_parent(id);
+
+        let mut removed = Vec::new();
+        let mut pending = vec![id];
+        while let Some(current_id) = pending.pop(){
+            if let Some(node) = self.nodes[current_id].take(){
+                pending.extend(node.children_id.clone());
+                self.free.push(current_id);
+                removed.push((current_id, node));
+            }
+        }
+        self.adjust_subtree_size(parent_id, -(removed.len() as isize));
+        Some((parent_id, child_index, removed))
+    }
+
+    /// Removes `id` from its parent's `children_id`, if it has a parent
+    fn detach_from_parent(&mut self, id: usize){
+        if let Some(parent_id) = self.node(id).parent_id{
+            self.node_mut(parent_id).children_id.retain(|&child_id| child_id != id);
+        }
+    }
+
+    /// Records the current state so a later [`Tree::rewind`] can undo every `add`/`remove`
+    /// performed since.
+    ///
+    /// # Returns
+    ///
+    /// * `CheckpointId` - An id for this checkpoint (informational; `rewind` always
+    ///     undoes the most recently created checkpoint still on the stack)
+    pub fn checkpoint(&mut self)->CheckpointId{
+        self.journal.push(Vec::new());
+        if let Some(max) = self.max_checkpoints{
+            while self.journal.len() > max{
+                self.journal.remove(0);
+            }
+        }
+        let id = self.next_checkpoint_id;
+        self.next_checkpoint_id += 1;
+        id
+    }
+
+    /// Reverts every `add`/`remove` performed since the most recent checkpoint. Stacked
+    /// checkpoints are peeled off one at a time: calling `rewind` repeatedly undoes
+    /// progressively older checkpoints.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `false` if no checkpoint exists to rewind to
+    pub fn rewind(&mut self)->bool{
+        let segment = match self.journal.pop(){
+            Some(segment) => segment,
+            None => return false,
+        };
+        for op in segment.into_iter().rev(){
+            self.apply_undo(op);
+        }
+        true
+    }
+
+    /// Pushes `op` onto the most recent checkpoint's journal, if any checkpoint is open
+    fn record(&mut self, op: UndoOp<T>){
+        if let Some(segment) = self.journal.last_mut(){
+            segment.push(op);
+        }
+    }
+
+    /// Replays a single inverse operation, without journaling it (`rewind` has already
+    /// popped the segment being undone, so journaling here would push these ops onto the
+    /// next-older checkpoint's segment)
+    fn apply_undo(&mut self, op: UndoOp<T>){
+        match op{
+            UndoOp::UndoAdd{ id } => {
+                self.detach_and_free_subtree(id);
+            }
+            UndoOp::UndoRemove{ id, node, size_anchor, relink } => {
+                self.nodes[id] = Some(node);
+                self.free.retain(|&free_id| free_id != id);
+                if let Some((parent_id, child_index)) = relink{
+                    let children = &mut self.node_mut(parent_id).children_id;
+                    let idx = child_index.min(children.len());
+                    children.insert(idx, id);
+                }
+                self.adjust_subtree_size(size_anchor, 1);
+            }
+            UndoOp::UndoReattach{ id, node, parent_id, child_index, reattached_children } => {
+                if let Some(parent_id) = parent_id{
+                    self.node_mut(parent_id).children_id.retain(|child_id| !reattached_children.contains(child_id));
+                }
+                for &child_id in &reattached_children{
+                    let child = self.node_mut(child_id);
+                    child.parent_id = Some(id);
+                    child.depth_level += 1;
+                }
+
+                self.nodes[id] = Some(node);
+                self.free.retain(|&free_id| free_id != id);
+                if let Some(parent_id) = parent_id{
+                    let children = &mut self.node_mut(parent_id).children_id;
+                    let idx = child_index.min(children.len());
+                    children.insert(idx, id);
+                }
+                self.adjust_subtree_size(parent_id, 1);
+            }
+        }
+    }
+
     pub fn find(&self, predicate: &dyn Fn(&T)->bool)->Option<usize>{
         for (i, node) in self.nodes.iter().enumerate(){
-            if predicate(&node.value){
-                return Some(i);
+            if let Some(node) = node{
+                if predicate(&node.value){
+                    return Some(i);
+                }
             }
         }
         None
@@ -117,17 +675,17 @@ impl<T> Tree<T>{
 
     /// Returns the last node if the branch matches from the root
     /// to the last node
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `branch` - A vector of values that represents the branch
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Option<T>` - The last node matching branch, Some value or None, whether it matched or not
-    /// 
+    ///
     /// # Example
-    /// 
+    ///
     /// ```
     /// let tree = Tree::<u32>::new(0);
     /// let tree = tree.add(1, 0);
@@ -145,21 +703,21 @@ impl<T> Tree<T>{
 
     /// Returns the last node if the branch matches from the root, using a predicate
     /// to compare the branch value with the tree values
-    /// Useful in case your T type either doesn't implement PartialEq 
+    /// Useful in case your T type either doesn't implement PartialEq
     /// or you wanna compare a really specific value.
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * `branch` - A vector of values that represents the branch
     /// * `predicate` - A function that receives the branch value and the tree value
-    /// 
+    ///
     /// # Returns
-    /// 
+    ///
     /// * `Option<T>` - The last node matching branch, wrapped in Option<T> or None,
     ///     whether it matched or not
-    /// 
+    ///
     /// # Example
-    /// 
+    ///
     /// ```
     /// let tree = Tree::<u32>::new(0);
     /// let tree = tree.add(1, 0);
@@ -171,25 +729,24 @@ impl<T> Tree<T>{
     ///     &|branch_value, tree_value| branch_value == tree_value
     /// );
     /// let node = node_option.unwrap_or_default().value;
-    /// 
+    ///
     /// assert_eq!(node.value, 3);
     pub fn matches_branch_predicated<U>(&self, branch:Vec<U>, predicate: &dyn Fn(&U, &T)->bool)->Option<Node<T>> where T: Clone{
-        let mut current_nodes_id = [0].to_vec();
-        let mut current_node_children_id: Vec<usize> = Vec::new();
+        let mut current_nodes_id = [self.root_id].to_vec();
         for (id,value) in branch.iter().enumerate(){
             // this is for the case where the node has children
             if (current_nodes_id.len() > 0){
                 for &current_node_id in &current_nodes_id.clone(){
-                    let node = &self.nodes[current_node_id];
+                    let node = self.node(current_node_id);
                     if predicate(&value,&node.value){
                         current_nodes_id = node.children_id.clone();
 
                         // if the node has no children, return it
                         if current_nodes_id.len() == 0 {
-                            return Some(self.nodes[current_node_id].clone());
+                            return Some(self.node(current_node_id).clone());
                         // if this is the last value in the branch, return it
                         }else if id == branch.len() - 1{
-                            return Some(self.nodes[current_node_id].clone());
+                            return Some(self.node(current_node_id).clone());
                         }else{
                             break;
                         }
@@ -199,6 +756,155 @@ impl<T> Tree<T>{
         }
         return None;
     }
+
+    /// Returns every node reached while matching `branch` against the tree as a prefix,
+    /// stopping at the first value that diverges (one node per successfully matched step).
+    ///
+    /// # Arguments
+    ///
+    /// * `branch` - A vector of values that represents the branch
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&Node<T>>` - The nodes matched along the branch, in order, empty if the
+    ///     root itself doesn't match
+    pub fn find_prefixes(&self, branch: Vec<T>)->Vec<&Node<T>> where T: PartialEq{
+        self.find_prefixes_predicated(branch, &|branch_value, tree_value| branch_value == tree_value)
+    }
+
+    /// Returns every node reached while matching `branch` against the tree as a prefix,
+    /// using a predicate to compare the branch value with the tree values. Useful in case
+    /// your `T` type either doesn't implement `PartialEq` or you wanna compare a really
+    /// specific value.
+    ///
+    /// # Arguments
+    ///
+    /// * `branch` - A vector of values that represents the branch
+    /// * `predicate` - A function that receives the branch value and the tree value
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<&Node<T>>` - The nodes matched along the branch, in order, empty if the
+    ///     root itself doesn't match
+    pub fn find_prefixes_predicated<U>(&self, branch: Vec<U>, predicate: &dyn Fn(&U, &T)->bool)->Vec<&Node<T>>{
+        let mut matched = Vec::new();
+        let mut current_nodes_id = [self.root_id].to_vec();
+
+        for value in branch.iter(){
+            if current_nodes_id.len() == 0{
+                break;
+            }
+
+            let mut matched_this_step = false;
+            for &current_node_id in &current_nodes_id{
+                let node = self.node(current_node_id);
+                if predicate(value, &node.value){
+                    matched.push(node);
+                    current_nodes_id = node.children_id.clone();
+                    matched_this_step = true;
+                    break;
+                }
+            }
+
+            if !matched_this_step{
+                break;
+            }
+        }
+
+        matched
+    }
+
+    /// Returns the deepest node still reachable before `branch` diverges from the tree
+    ///
+    /// # Arguments
+    ///
+    /// * `branch` - A vector of values that represents the branch
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Node<T>>` - The deepest matching node, or `None` if even the root doesn't match
+    pub fn find_longest_prefix(&self, branch: Vec<T>)->Option<&Node<T>> where T: PartialEq{
+        self.find_longest_prefix_predicated(branch, &|branch_value, tree_value| branch_value == tree_value)
+    }
+
+    /// Returns the deepest node still reachable before `branch` diverges from the tree,
+    /// using a predicate to compare the branch value with the tree values
+    ///
+    /// # Arguments
+    ///
+    /// * `branch` - A vector of values that represents the branch
+    /// * `predicate` - A function that receives the branch value and the tree value
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&Node<T>>` - The deepest matching node, or `None` if even the root doesn't match
+    pub fn find_longest_prefix_predicated<U>(&self, branch: Vec<U>, predicate: &dyn Fn(&U, &T)->bool)->Option<&Node<T>>{
+        self.find_prefixes_predicated(branch, predicate).into_iter().last()
+    }
+
+    /// Declares `id` the new canonical root, discarding every node that isn't `id` or one
+    /// of its descendants. `id`'s `depth_level` becomes 0, and every retained descendant's
+    /// `depth_level` shifts down to match. This is a one-way operation: once finalized,
+    /// the discarded history can no longer be rewound, so any open checkpoints are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the node to finalize as the new root
+    pub fn finalize(&mut self, id: usize){
+        if !self.is_live(id){
+            return;
+        }
+
+        let retained: HashSet<usize> = self.iter_bfs(id).map(|(retained_id, _)| retained_id).collect();
+        let old_depth = self.node(id).depth_level;
+
+        let to_discard: Vec<usize> = self.nodes.iter().enumerate()
+            .filter_map(|(nid, slot)| slot.as_ref().map(|_| nid))
+            .filter(|nid| !retained.contains(nid))
+            .collect();
+        for nid in to_discard{
+            self.nodes[nid] = None;
+            self.free.push(nid);
+        }
+
+        for &retained_id in &retained{
+            if retained_id != id{
+                self.node_mut(retained_id).depth_level -= old_depth;
+            }
+        }
+        let root = self.node_mut(id);
+        root.parent_id = None;
+        root.depth_level = 0;
+        self.root_id = id;
+
+        // discarded nodes may be referenced by older undo entries; there's no
+        // meaningful way to rewind past a finalization, so history starts fresh
+        self.journal.clear();
+    }
+
+    /// Removes every branch whose root value satisfies `pred`, starting the search from
+    /// the tree's current root (`self.root_id`, which `finalize` may have moved). A
+    /// branch whose root doesn't match is still walked into, so a descendant further
+    /// down can still be pruned. The canonical root itself can never be removed (see
+    /// [`Tree::remove_subtree`]), so a match there is ignored and its children are still
+    /// walked into, same as a non-match.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred` - Tested against each live node; matching nodes are removed with their
+    ///     whole subtree
+    pub fn prune_where(&mut self, pred: impl Fn(&Node<T>)->bool){
+        let mut frontier = vec![self.root_id];
+        while let Some(id) = frontier.pop(){
+            if !self.is_live(id){
+                continue;
+            }
+            if pred(self.node(id)) && id != self.root_id && self.remove_subtree(id){
+                continue;
+            }
+            frontier.extend(self.node(id).children_id.clone());
+        }
+    }
 }
 
 
@@ -255,7 +961,7 @@ mod tests{
             .add(1,0)
             .add(2,0)
             .add(3,0);
-        
+
         let children = tree.get_children(0);
 
         assert_eq!(children.len(), 3);
@@ -270,7 +976,7 @@ mod tests{
         let tree = setup_tree();
         // it should return the last node of this branch
         // in this case, 3
-        let searched_value = tree.matches_branch([10,1,2,3].to_vec()); 
+        let searched_value = tree.matches_branch([10,1,2,3].to_vec());
         let node = if searched_value.is_some() {searched_value.unwrap()} else {return};
         let value = node.value;
 
@@ -282,7 +988,7 @@ mod tests{
         let tree = setup_tree();
         // it should return the last node of this branch
         // in this case, 3
-        let searched_value = tree.matches_branch([10,1,2].to_vec()); 
+        let searched_value = tree.matches_branch([10,1,2].to_vec());
         let node = if searched_value.is_some() {searched_value.unwrap()} else {return};
         let value = node.value;
 
@@ -315,4 +1021,385 @@ mod tests{
         assert_eq!(value, (3,8));
         assert_eq!(node.parent_id, Some(4));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn remove_subtree_drops_descendants_and_frees_slots(){
+        let mut tree = setup_tree();
+        // node 1 (value 1) has children 4 (value 2) and 5 (value 3)
+        assert!(tree.remove_subtree(1));
+
+        assert_eq!(tree.get_children(0).len(), 2);
+        assert!(!tree.is_live(1));
+        assert!(!tree.is_live(4));
+        assert!(!tree.is_live(5));
+
+        // the freed slots should be reused instead of growing the vec
+        let len_before = tree.nodes.len();
+        let tree = tree.add(9, 0);
+        assert_eq!(tree.nodes.len(), len_before);
+    }
+
+    #[test]
+    fn remove_with_reattach_policy_promotes_children(){
+        let mut tree = setup_tree();
+        // node 2 (value 2) has children 7 (value 4) and 8 (value 8)
+        assert!(tree.remove(2, OrphanPolicy::ReattachChildren));
+
+        assert!(!tree.is_live(2));
+        let root_children: Vec<u32> = tree.get_children(0).iter().map(|n| n.value).collect();
+        assert!(root_children.contains(&4));
+        assert!(root_children.contains(&8));
+        assert_eq!(tree.node(7).depth_level, 1);
+    }
+
+    #[test]
+    fn remove_missing_node_returns_false(){
+        let mut tree = setup_tree();
+        assert!(tree.remove_subtree(1));
+        assert!(!tree.remove_subtree(1));
+        assert!(!tree.remove(100, OrphanPolicy::DropSubtree));
+    }
+
+    #[test]
+    fn remove_refuses_to_remove_the_canonical_root(){
+        let mut tree = setup_tree();
+        assert!(!tree.remove(0, OrphanPolicy::ReattachChildren));
+        assert!(!tree.remove_subtree(0));
+        assert!(tree.is_live(0));
+
+        // previously this panicked because root-anchored lookups assumed node 0 was live
+        let found = tree.find_prefixes([10].to_vec());
+        assert_eq!(found.iter().map(|n| n.value).collect::<Vec<u32>>(), vec![10]);
+    }
+
+    #[test]
+    fn bfs_visits_level_by_level(){
+        let tree = setup_tree();
+        let ids: Vec<usize> = tree.iter_bfs(0).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0,1,2,3,4,5,7,8,6]);
+    }
+
+    #[test]
+    fn dfs_preorder_visits_root_before_children(){
+        let tree = setup_tree();
+        let ids: Vec<usize> = tree.iter_dfs_preorder(0).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0,1,4,6,5,2,7,8,3]);
+    }
+
+    #[test]
+    fn dfs_postorder_visits_children_before_root(){
+        let tree = setup_tree();
+        let ids: Vec<usize> = tree.iter_dfs_postorder(0).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![6,4,5,1,7,8,2,3,0]);
+    }
+
+    #[test]
+    fn traversal_skips_removed_nodes(){
+        let mut tree = setup_tree();
+        tree.remove_subtree(1);
+        let ids: Vec<usize> = tree.iter_bfs(0).map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![0,2,3,7,8]);
+    }
+
+    #[test]
+    fn find_bfs_and_find_dfs_preorder_return_first_match_within_subtree(){
+        let tree = setup_tree();
+        assert_eq!(tree.find_bfs(0, &|&val| val == 3), Some(5));
+        assert_eq!(tree.find_dfs_preorder(0, &|&val| val == 3), Some(6));
+        assert_eq!(tree.find_bfs(2, &|&val| val == 3), None);
+    }
+
+    #[test]
+    fn fold_subtree_sums_descendant_values(){
+        let tree = setup_tree();
+        // subtree rooted at 1 (value 1) holds 1,2,3,3 (ids 1,4,5,6)
+        assert_eq!(tree.fold_subtree(1, 0, |acc, &val| acc + val), 1 + 2 + 3 + 3);
+        assert_eq!(tree.fold_subtree(0, 0, |acc, &val| acc + val), 10+1+2+5+2+3+3+4+8);
+    }
+
+    #[test]
+    fn subtree_size_and_descendants_match_fold_based_counts(){
+        let tree = setup_tree();
+        assert_eq!(tree.subtree_size(0), 9);
+        assert_eq!(tree.subtree_size(1), 4);
+        assert_eq!(tree.subtree_size(6), 1);
+        assert_eq!(tree.descendants(1).len() + 1, tree.subtree_size(1));
+        assert_eq!(tree.fold_subtree(1, 0, |acc, _| acc + 1), tree.subtree_size(1));
+        assert_eq!(tree.fold_subtree(0, 0, |acc, _| acc + 1), tree.subtree_size(0));
+    }
+
+    #[test]
+    fn subtree_size_updates_incrementally_on_add_and_remove(){
+        let tree = setup_tree();
+        assert_eq!(tree.subtree_size(0), 9);
+
+        let tree = tree.add(42, 6);
+        assert_eq!(tree.subtree_size(6), 2);
+        assert_eq!(tree.subtree_size(1), 5);
+        assert_eq!(tree.subtree_size(0), 10);
+
+        let mut tree = tree;
+        tree.remove_subtree(4);
+        assert_eq!(tree.subtree_size(1), 2);
+        assert_eq!(tree.subtree_size(0), 7);
+
+        tree.remove(2, OrphanPolicy::ReattachChildren);
+        assert_eq!(tree.subtree_size(0), 6);
+    }
+
+    #[test]
+    fn lowest_common_ancestor_of_cousins(){
+        let tree = setup_tree();
+        // 6 (depth 3, under 4 under 1) and 7 (depth 2, under 2) share root 0
+        assert_eq!(tree.lowest_common_ancestor(6, 7), Some(0));
+    }
+
+    #[test]
+    fn lowest_common_ancestor_when_one_is_ancestor_of_other(){
+        let tree = setup_tree();
+        // 1 is an ancestor of 6
+        assert_eq!(tree.lowest_common_ancestor(1, 6), Some(1));
+        assert_eq!(tree.lowest_common_ancestor(4, 4), Some(4));
+    }
+
+    #[test]
+    fn lowest_common_ancestor_returns_none_for_removed_node(){
+        let mut tree = setup_tree();
+        tree.remove_subtree(1);
+        assert_eq!(tree.lowest_common_ancestor(6, 2), None);
+    }
+
+    #[test]
+    fn path_between_goes_up_to_lca_and_back_down(){
+        let tree = setup_tree();
+        assert_eq!(tree.path_between(6, 7), Some(vec![6,4,1,0,2,7]));
+        assert_eq!(tree.path_between(1, 6), Some(vec![1,4,6]));
+        assert_eq!(tree.path_between(4, 4), Some(vec![4]));
+    }
+
+    #[test]
+    fn find_prefixes_returns_one_node_per_matched_step(){
+        let tree = setup_tree();
+        let prefixes = tree.find_prefixes([10,1,2,3].to_vec());
+        let values: Vec<u32> = prefixes.iter().map(|node| node.value).collect();
+        assert_eq!(values, vec![10,1,2,3]);
+    }
+
+    #[test]
+    fn find_prefixes_stops_at_divergence(){
+        let tree = setup_tree();
+        let prefixes = tree.find_prefixes([10,1,2,99].to_vec());
+        let values: Vec<u32> = prefixes.iter().map(|node| node.value).collect();
+        assert_eq!(values, vec![10,1,2]);
+    }
+
+    #[test]
+    fn find_prefixes_empty_when_root_does_not_match(){
+        let tree = setup_tree();
+        assert!(tree.find_prefixes([0,1].to_vec()).is_empty());
+    }
+
+    #[test]
+    fn find_longest_prefix_returns_deepest_reachable_node(){
+        let tree = setup_tree();
+        let deepest = tree.find_longest_prefix([10,1,2,99].to_vec()).unwrap();
+        assert_eq!(deepest.value, 2);
+
+        let full_match = tree.find_longest_prefix([10,1,2,3].to_vec()).unwrap();
+        assert_eq!(full_match.value, 3);
+    }
+
+    #[test]
+    fn find_longest_prefix_predicated_reuses_custom_comparison(){
+        let tree = setup_complex_tree();
+        let deepest = tree.find_longest_prefix_predicated::<u32>([0,1,2,99].to_vec(),
+            &|branch_value, tree_value| *branch_value == tree_value.0
+        ).unwrap();
+        assert_eq!(deepest.value, (2,2));
+    }
+
+    #[test]
+    fn rewind_without_a_checkpoint_returns_false(){
+        let mut tree = setup_tree();
+        assert!(!tree.rewind());
+    }
+
+    #[test]
+    fn rewind_undoes_adds_back_to_the_checkpoint(){
+        let mut tree = setup_tree();
+        tree.checkpoint();
+        let tree = tree.add(42, 0).add(43, 0);
+        let mut tree = tree;
+        assert_eq!(tree.nodes.len(), 11);
+
+        assert!(tree.rewind());
+        assert_eq!(tree.get_children(0).len(), 3);
+        assert_eq!(tree.subtree_size(0), 9);
+        // the reclaimed slots should be handed back out by the next add, so the vec
+        // doesn't need to grow past the high-water mark left by the undone adds
+        let tree = tree.add(99, 0);
+        assert_eq!(tree.nodes.len(), 11);
+    }
+
+    #[test]
+    fn rewind_undoes_remove_subtree(){
+        let mut tree = setup_tree();
+        tree.checkpoint();
+        tree.remove_subtree(1);
+        assert!(!tree.is_live(1));
+
+        assert!(tree.rewind());
+        assert!(tree.is_live(1));
+        assert!(tree.is_live(4));
+        assert!(tree.is_live(6));
+        assert_eq!(tree.get_children(0).len(), 3);
+        assert_eq!(tree.subtree_size(0), 9);
+        assert_eq!(tree.subtree_size(1), 4);
+        let values: Vec<u32> = tree.get_children(0).iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![1,2,5]);
+    }
+
+    #[test]
+    fn rewind_undoes_reattach_removal(){
+        let mut tree = setup_tree();
+        tree.checkpoint();
+        tree.remove(2, OrphanPolicy::ReattachChildren);
+        assert!(!tree.is_live(2));
+
+        assert!(tree.rewind());
+        assert!(tree.is_live(2));
+        assert_eq!(tree.get_children(2).iter().map(|n| n.value).collect::<Vec<u32>>(), vec![4,8]);
+        assert_eq!(tree.node(7).depth_level, 2);
+        assert_eq!(tree.node(7).parent_id, Some(2));
+        assert_eq!(tree.subtree_size(0), 9);
+        let values: Vec<u32> = tree.get_children(0).iter().map(|n| n.value).collect();
+        assert_eq!(values, vec![1,2,5]);
+    }
+
+    #[test]
+    fn stacked_checkpoints_peel_off_one_at_a_time(){
+        let mut tree = setup_tree();
+        tree.checkpoint();
+        let tree = tree.add(42, 0);
+        let mut tree = tree;
+        tree.checkpoint();
+        tree.remove_subtree(1);
+
+        assert!(tree.rewind());
+        assert!(tree.is_live(1));
+        assert_eq!(tree.get_children(0).len(), 4);
+
+        assert!(tree.rewind());
+        assert_eq!(tree.get_children(0).len(), 3);
+
+        assert!(!tree.rewind());
+    }
+
+    #[test]
+    fn max_checkpoints_drops_oldest_segment(){
+        let mut tree = setup_tree().with_max_checkpoints(1);
+        tree.checkpoint();
+        let tree = tree.add(42, 0);
+        let mut tree = tree;
+        tree.checkpoint();
+        let tree = tree.add(43, 0);
+        let mut tree = tree;
+
+        // only the newest checkpoint survived, so a single rewind exhausts the stack
+        assert!(tree.rewind());
+        assert!(!tree.rewind());
+        assert_eq!(tree.get_children(0).len(), 4);
+    }
+
+    #[test]
+    fn finalize_discards_everything_outside_the_new_root_subtree(){
+        let mut tree = setup_tree();
+        // re-root at node 1 (value 1), whose descendants are 4,5,6
+        tree.finalize(1);
+
+        assert!(tree.is_live(1));
+        assert!(tree.is_live(4));
+        assert!(tree.is_live(5));
+        assert!(tree.is_live(6));
+        assert!(!tree.is_live(0));
+        assert!(!tree.is_live(2));
+        assert!(!tree.is_live(3));
+        assert!(!tree.is_live(7));
+        assert!(!tree.is_live(8));
+
+        assert_eq!(tree.node(1).depth_level, 0);
+        assert_eq!(tree.node(1).parent_id, None);
+        assert_eq!(tree.node(4).depth_level, 1);
+        assert_eq!(tree.node(6).depth_level, 2);
+        assert_eq!(tree.subtree_size(1), 4);
+    }
+
+    #[test]
+    fn finalize_on_missing_node_is_a_no_op(){
+        let mut tree = setup_tree();
+        tree.finalize(100);
+        assert!(tree.is_live(0));
+        assert_eq!(tree.get_children(0).len(), 3);
+    }
+
+    #[test]
+    fn finalize_clears_open_checkpoints(){
+        let mut tree = setup_tree();
+        tree.checkpoint();
+        tree.finalize(1);
+        assert!(!tree.rewind());
+    }
+
+    #[test]
+    fn prune_where_removes_matching_branches_and_their_descendants(){
+        let mut tree = setup_tree();
+        // node 4 (value 2, child of 1) should be pruned along with its child 6 (value 3)
+        tree.prune_where(|node| node.value == 2 && node.depth_level == 2);
+
+        assert!(!tree.is_live(4));
+        assert!(!tree.is_live(6));
+        assert!(tree.is_live(1));
+        assert!(tree.is_live(5));
+        assert_eq!(tree.get_children(1).iter().map(|n| n.value).collect::<Vec<u32>>(), vec![3]);
+    }
+
+    #[test]
+    fn prune_where_and_branch_matching_follow_root_after_finalize(){
+        let mut tree = setup_tree();
+        // re-root at 1 (value 1); its live descendants are 4 (value 2), 5 (value 3), 6 (value 3)
+        tree.finalize(1);
+
+        tree.prune_where(|node| node.value == 3 && node.depth_level == 1);
+        assert!(!tree.is_live(5));
+        assert!(tree.is_live(4));
+        assert!(tree.is_live(6));
+
+        let searched = tree.matches_branch([1,2,3].to_vec());
+        assert_eq!(searched.unwrap().value, 3);
+
+        let prefixes = tree.find_prefixes([1,2,3].to_vec());
+        assert_eq!(prefixes.iter().map(|n| n.value).collect::<Vec<u32>>(), vec![1,2,3]);
+    }
+
+    #[test]
+    fn prune_where_matching_the_root_still_descends_into_its_children(){
+        let mut tree = setup_tree();
+        // root (id 0, value 10) is the only node with this value; it can't be removed,
+        // so matching it must not stop the walk from reaching its children
+        tree.prune_where(|node| node.value == 10);
+
+        assert!(tree.is_live(0));
+        assert_eq!(tree.get_children(0).len(), 3);
+    }
+
+    #[test]
+    fn iterators_degrade_to_empty_for_an_out_of_bounds_root(){
+        let tree = setup_tree();
+        let out_of_bounds = tree.nodes.len() + 5;
+
+        assert_eq!(tree.iter_bfs(out_of_bounds).count(), 0);
+        assert_eq!(tree.iter_dfs_preorder(out_of_bounds).count(), 0);
+        assert_eq!(tree.iter_dfs_postorder(out_of_bounds).count(), 0);
+        assert_eq!(tree.find_bfs(out_of_bounds, &|_| true), None);
+    }
+}